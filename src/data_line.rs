@@ -5,6 +5,7 @@ use std::net::{AddrParseError, IpAddr, Ipv4Addr};
 use std::str::FromStr;
 use multistr::StringVec;
 use multistr::Iter as SVIter;
+use super::punycode;
 
 /// Characters which aren't allowed in URLs.
 static INVALID_CHARS: &[char] = &[
@@ -64,6 +65,163 @@ impl DataLine {
             hosts: self.hosts,
         }
     }
+
+    /// Adds a host to this line, if it isn't already present.
+    pub fn add_host(&mut self, host: &str) {
+        if self.hosts.iter().all(|h| h != host) {
+            self.hosts.push(host);
+        }
+    }
+
+    /// Removes a host from this line, reporting whether it was present.
+    pub fn remove_host(&mut self, host: &str) -> bool {
+        let before = self.hosts.iter().count();
+        let kept: Vec<String> = self.hosts
+            .iter()
+            .filter(|h| *h != host)
+            .map(ToOwned::to_owned)
+            .collect();
+        if kept.len() == before {
+            false
+        } else {
+            self.hosts = kept.iter().map(|s| &**s).collect();
+            true
+        }
+    }
+
+    /// Checks whether this line has no hosts left.
+    pub fn is_empty(&self) -> bool {
+        self.hosts().next().is_none()
+    }
+
+    /// Parses a line, validating each host against a `ValidationPolicy` instead of the default
+    /// WHATWG blacklist.
+    pub fn from_str_with_policy(s: &str, policy: ValidationPolicy) -> Result<DataLine, DataParseError> {
+        parse_with_host_transform(s, |host| policy.validate(host).map(|()| host.to_owned()))
+    }
+
+    /// Parses a line, enforcing strict RFC 1035 label syntax: each label is 1-63 bytes, the
+    /// total hostname is at most 253 bytes, labels contain only `[A-Za-z0-9-]`, and no label
+    /// begins or ends with a hyphen.
+    pub fn from_str_strict(s: &str) -> Result<DataLine, DataParseError> {
+        DataLine::from_str_with_policy(s, ValidationPolicy::strict())
+    }
+
+    /// Parses a line like `from_str`, but normalizes non-ASCII hostname labels to their
+    /// punycode (`xn--`) form first.
+    ///
+    /// Internationalized domains such as `m\u{fc}ller.example` are stored as
+    /// `xn--mller-kva.example`, matching the ASCII-compatible form the system resolver actually
+    /// looks up. Pure-ASCII hosts are stored exactly as `from_str` would store them.
+    pub fn from_str_idna(s: &str) -> Result<DataLine, DataParseError> {
+        parse_with_host_transform(s, |host| idna_encode_host(host))
+    }
+}
+
+/// Shared skeleton for parsing a `DataLine`: split on the first whitespace, parse the IP, then
+/// run each whitespace-separated host through `host_transform` (which validates and/or
+/// normalizes it), reject the WHATWG-blacklisted characters in the transformed result, and
+/// reject an IPv4-literal-as-hostname.
+///
+/// The blacklist check runs on the *transformed* host (not the raw input) so that a transform
+/// like `idna_encode_host`, which leaves ASCII labels untouched, can't smuggle a character like
+/// `#` past validation.
+fn parse_with_host_transform<F>(s: &str, mut host_transform: F) -> Result<DataLine, DataParseError>
+where
+    F: FnMut(&str) -> Result<String, DataParseError>,
+{
+    let s = s.trim();
+    if let Some(idx) = s.find(char::is_whitespace) {
+        let ip = s[..idx].parse().map_err(|err| {
+            DataParseError::BadIp(err, s[..idx].to_owned())
+        })?;
+        let mut hosts = StringVec::new();
+        for host in s[idx..].split_whitespace() {
+            let host = host_transform(host)?;
+            // https://url.spec.whatwg.org/#host-parsing
+            if let Some(idx) = host.find(INVALID_CHARS) {
+                return Err(DataParseError::BadHost(
+                    host[idx..].chars().next().unwrap(),
+                    host,
+                ));
+            } else if let Ok(ipv4) = host.parse::<Ipv4Addr>() {
+                return Err(DataParseError::HostWasIp(ipv4));
+            }
+            hosts.push(&host);
+        }
+        Ok(DataLine {
+            ip: ip,
+            hosts: hosts,
+        })
+    } else {
+        Err(DataParseError::NoInternalSpace)
+    }
+}
+
+/// Maximum length of a single DNS label, per RFC 1035.
+const MAX_LABEL_LEN: usize = 63;
+
+/// Maximum length of a full DNS name, per RFC 1035.
+const MAX_NAME_LEN: usize = 253;
+
+/// Controls how strictly a hostname is validated.
+///
+/// The default `DataLine::from_str` only rejects a WHATWG blacklist of characters; a
+/// `ValidationPolicy` instead enforces RFC 1035 label syntax (length limits, allowed characters,
+/// and hyphen placement).
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct ValidationPolicy {
+    allow_underscore: bool,
+}
+impl ValidationPolicy {
+    /// The strict policy: labels may contain only `[A-Za-z0-9-]`.
+    pub fn strict() -> ValidationPolicy {
+        ValidationPolicy { allow_underscore: false }
+    }
+
+    /// The strict policy, but also permitting underscores in labels (common in practice for
+    /// things like SRV and DKIM records, though not strictly RFC 1035).
+    pub fn strict_with_underscore() -> ValidationPolicy {
+        ValidationPolicy { allow_underscore: true }
+    }
+
+    /// Validates a host against this policy.
+    pub fn validate(&self, host: &str) -> Result<(), DataParseError> {
+        if host.len() > MAX_NAME_LEN {
+            return Err(DataParseError::NameTooLong(host.to_owned()));
+        }
+        for label in host.split('.') {
+            if label.is_empty() {
+                return Err(DataParseError::EmptyLabel(host.to_owned()));
+            }
+            if label.len() > MAX_LABEL_LEN {
+                return Err(DataParseError::LabelTooLong(host.to_owned()));
+            }
+            if label.starts_with('-') || label.ends_with('-') {
+                return Err(DataParseError::HyphenPosition(host.to_owned()));
+            }
+            for ch in label.chars() {
+                let ok = ch.is_ascii_alphanumeric() || ch == '-' ||
+                    (self.allow_underscore && ch == '_');
+                if !ok {
+                    return Err(DataParseError::BadHost(ch, host.to_owned()));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Encodes each dot-separated label of a host to its ASCII-compatible form.
+fn idna_encode_host(host: &str) -> Result<String, DataParseError> {
+    let mut labels = Vec::new();
+    for label in host.split('.') {
+        match punycode::encode_label(label) {
+            Some(encoded) => labels.push(encoded),
+            None => return Err(DataParseError::IdnaError(label.to_owned())),
+        }
+    }
+    Ok(labels.join("."))
 }
 
 /// Minifies a list of data lines.`
@@ -144,6 +302,21 @@ pub enum DataParseError {
 
     /// The IP failed to parse.
     BadIp(AddrParseError, String),
+
+    /// A hostname label couldn't be IDNA/punycode-encoded.
+    IdnaError(String),
+
+    /// A label of the host was longer than 63 bytes.
+    LabelTooLong(String),
+
+    /// The full host was longer than 253 bytes.
+    NameTooLong(String),
+
+    /// The host had an empty label, e.g. from a doubled or leading/trailing `.`.
+    EmptyLabel(String),
+
+    /// A label of the host began or ended with a hyphen.
+    HyphenPosition(String),
 }
 impl Error for DataParseError {
     fn description(&self) -> &str {
@@ -154,6 +327,11 @@ impl Error for DataParseError {
                 "a host was invalid because it contains an invalid character"
             }
             DataParseError::BadIp(_, _) => "could not parse IP",
+            DataParseError::IdnaError(_) => "a hostname label could not be IDNA-encoded",
+            DataParseError::LabelTooLong(_) => "a hostname label was longer than 63 bytes",
+            DataParseError::NameTooLong(_) => "the hostname was longer than 253 bytes",
+            DataParseError::EmptyLabel(_) => "the hostname had an empty label",
+            DataParseError::HyphenPosition(_) => "a hostname label began or ended with a hyphen",
         }
     }
     fn cause(&self) -> Option<&Error> {
@@ -180,6 +358,21 @@ impl fmt::Display for DataParseError {
                 )
             }
             DataParseError::BadIp(_, ref ip) => write!(f, "could not parse {:?} as an IP", ip),
+            DataParseError::IdnaError(ref label) => {
+                write!(f, "the label {:?} could not be IDNA-encoded", label)
+            }
+            DataParseError::LabelTooLong(ref host) => {
+                write!(f, "a label of {:?} was longer than 63 bytes", host)
+            }
+            DataParseError::NameTooLong(ref host) => {
+                write!(f, "{:?} was longer than 253 bytes", host)
+            }
+            DataParseError::EmptyLabel(ref host) => {
+                write!(f, "{:?} had an empty label", host)
+            }
+            DataParseError::HyphenPosition(ref host) => {
+                write!(f, "a label of {:?} began or ended with a hyphen", host)
+            }
         }
     }
 }
@@ -187,32 +380,7 @@ impl fmt::Display for DataParseError {
 impl FromStr for DataLine {
     type Err = DataParseError;
     fn from_str(s: &str) -> Result<DataLine, DataParseError> {
-        let s = s.trim();
-        if let Some(idx) = s.find(char::is_whitespace) {
-            let ip = s[..idx].parse().map_err(|err| {
-                DataParseError::BadIp(err, s[..idx].to_owned())
-            })?;
-            let mut hosts = StringVec::new();
-            for host in s[idx..].split_whitespace() {
-                // https://url.spec.whatwg.org/#host-parsing
-                if let Some(idx) = host.find(INVALID_CHARS) {
-                    return Err(DataParseError::BadHost(
-                        host[idx..].chars().next().unwrap(),
-                        host.to_owned(),
-                    ));
-                } else if let Ok(ipv4) = host.parse::<Ipv4Addr>() {
-                    return Err(DataParseError::HostWasIp(ipv4));
-                } else {
-                    hosts.push(host);
-                }
-            }
-            Ok(DataLine {
-                ip: ip,
-                hosts: hosts,
-            })
-        } else {
-            Err(DataParseError::NoInternalSpace)
-        }
+        parse_with_host_transform(s, |host| Ok(host.to_owned()))
     }
 }
 
@@ -275,6 +443,80 @@ mod tests {
         assert_eq!(hosts, &["localhost", "localhost.localdomain", "lh"]);
     }
 
+    #[test]
+    fn strict_accepts_valid_name() {
+        let line = DataLine::from_str_strict("::1 foo-bar.example").unwrap();
+        let hosts: Vec<&str> = line.hosts().collect();
+        assert_eq!(hosts, &["foo-bar.example"]);
+    }
+
+    #[test]
+    fn strict_rejects_empty_label() {
+        let line: Result<DataLine, _> = DataLine::from_str_strict("::1 foo..bar");
+        assert_eq!(line, Err(DataParseError::EmptyLabel("foo..bar".to_owned())));
+    }
+
+    #[test]
+    fn strict_rejects_leading_hyphen() {
+        let line: Result<DataLine, _> = DataLine::from_str_strict("::1 -foo.example");
+        assert_eq!(
+            line,
+            Err(DataParseError::HyphenPosition("-foo.example".to_owned()))
+        );
+    }
+
+    #[test]
+    fn strict_rejects_long_label() {
+        let long_label = "a".repeat(64);
+        let host = format!("{}.example", long_label);
+        let line: Result<DataLine, _> = DataLine::from_str_strict(&format!("::1 {}", host));
+        assert_eq!(line, Err(DataParseError::LabelTooLong(host)));
+    }
+
+    #[test]
+    fn strict_rejects_underscore_by_default() {
+        let line: Result<DataLine, _> = DataLine::from_str_strict("::1 _dmarc.example");
+        if let Err(DataParseError::BadHost('_', host)) = line {
+            assert_eq!(host, "_dmarc.example");
+        } else {
+            panic!("not a bad host: {:?}", line);
+        }
+    }
+
+    #[test]
+    fn strict_with_underscore_allows_it() {
+        let line = DataLine::from_str_with_policy(
+            "::1 _dmarc.example",
+            ValidationPolicy::strict_with_underscore(),
+        ).unwrap();
+        let hosts: Vec<&str> = line.hosts().collect();
+        assert_eq!(hosts, &["_dmarc.example"]);
+    }
+
+    #[test]
+    fn idna_host() {
+        let line = DataLine::from_str_idna("::1 m\u{fc}ller.example").unwrap();
+        let hosts: Vec<&str> = line.hosts().collect();
+        assert_eq!(hosts, &["xn--mller-kva.example"]);
+    }
+
+    #[test]
+    fn idna_ascii_host_untouched() {
+        let line = DataLine::from_str_idna("::1 plain.example").unwrap();
+        let hosts: Vec<&str> = line.hosts().collect();
+        assert_eq!(hosts, &["plain.example"]);
+    }
+
+    #[test]
+    fn idna_rejects_invalid_chars_in_ascii_label() {
+        let line: Result<DataLine, _> = DataLine::from_str_idna("1.2.3.4 foo#bar");
+        if let Err(DataParseError::BadHost('#', host)) = line {
+            assert_eq!(host, "foo#bar");
+        } else {
+            panic!("not a bad host: {:?}", line);
+        }
+    }
+
     #[test]
     fn ascii_host() {
         let line: DataLine = "::1 the-quick-brown-fox-jumped-over-the-lazy-dog-0123456789.com"
@@ -0,0 +1,197 @@
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::net::IpAddr;
+use super::{DataLine, Line, LineReadError};
+
+/// A mutable, in-memory representation of `/etc/hosts`.
+///
+/// Unlike `HostsFile`, which only yields iterators over a reader, `HostsDocument` eagerly parses
+/// every line up front so that mappings can be added, removed, or rewritten, then written back
+/// out with comments and blank lines preserved. Lines are written in `Line`'s canonical layout,
+/// so the original spacing between fields is not kept.
+pub struct HostsDocument {
+    lines: Vec<Line<'static>>,
+}
+impl HostsDocument {
+    /// Loads and parses the data from `/etc/hosts`.
+    pub fn load() -> Result<HostsDocument, LineReadError> {
+        HostsDocument::read(BufReader::new(File::open("/etc/hosts")?))
+    }
+
+    /// Parses the data from a generic reader wrapped in a `BufReader`.
+    pub fn read_buffered<R: Read>(reader: R) -> Result<HostsDocument, LineReadError> {
+        HostsDocument::read(BufReader::new(reader))
+    }
+
+    /// Parses the data from a generic reader.
+    pub fn read<R: BufRead>(reader: R) -> Result<HostsDocument, LineReadError> {
+        let mut lines = Vec::new();
+        for line in reader.lines() {
+            lines.push(line?.parse()?);
+        }
+        Ok(HostsDocument { lines: lines })
+    }
+
+    /// Gets the lines in this document, in their original order.
+    pub fn lines(&self) -> &[Line<'static>] {
+        &self.lines
+    }
+
+    /// Gets a mutable view of the lines in this document, in their original order.
+    pub fn lines_mut(&mut self) -> &mut Vec<Line<'static>> {
+        &mut self.lines
+    }
+
+    /// Adds a host to an IP, appending it to the first existing line for that IP, or creating a
+    /// new line if none exists yet.
+    pub fn add_mapping(&mut self, ip: IpAddr, host: &str) {
+        for line in &mut self.lines {
+            if let Some(data) = line.data_mut() {
+                if data.ip() == ip {
+                    data.add_host(host);
+                    return;
+                }
+            }
+        }
+        self.lines.push(Line::from_data(DataLine::from_raw(ip, Some(host))));
+    }
+
+    /// Removes a host wherever it's mapped, dropping any containing line that becomes empty.
+    pub fn remove_host(&mut self, host: &str) {
+        for line in &mut self.lines {
+            if let Some(data) = line.data_mut() {
+                data.remove_host(host);
+            }
+        }
+        self.lines.retain(
+            |line| !line.data().map_or(false, DataLine::is_empty),
+        );
+    }
+
+    /// Removes every mapping for an IP, dropping the lines that held them.
+    pub fn remove_ip(&mut self, ip: IpAddr) {
+        self.lines.retain(
+            |line| line.data().map_or(true, |data| data.ip() != ip),
+        );
+    }
+
+    /// Repoints a host at a different IP, moving it out of any line it currently occupies.
+    pub fn set_host(&mut self, host: &str, ip: IpAddr) {
+        self.remove_host(host);
+        self.add_mapping(ip, host);
+    }
+
+    /// Writes this document back out, re-serializing every line via its `Display` impl.
+    ///
+    /// Comment text, blank lines, and line order survive, but each line is re-emitted in its
+    /// canonical layout: the IP is followed by two spaces, hosts are separated by one space, and a
+    /// comment is written as `# text` (after two spaces when it trails data). Whitespace-only
+    /// lines come back empty, and other spacing from the original file is not kept.
+    pub fn write_to<W: Write>(&self, mut w: W) -> io::Result<()> {
+        for line in &self.lines {
+            writeln!(w, "{}", line)?;
+        }
+        Ok(())
+    }
+
+    /// Writes this document back to `/etc/hosts`.
+    pub fn save(&self) -> io::Result<()> {
+        self.write_to(File::create("/etc/hosts")?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::{IpAddr, Ipv4Addr};
+    use super::HostsDocument;
+
+    static PRETTY: &str = "\
+# basic ones
+127.0.0.1  localhost localhost.localdomain
+0.0.0.0  allzeros  # nonstandard
+
+# others
+8.8.8.8  gdns  # this is the more common one
+";
+
+    #[test]
+    fn round_trip_unchanged() {
+        let doc = HostsDocument::read_buffered(PRETTY.as_bytes()).unwrap();
+        let mut out = Vec::new();
+        doc.write_to(&mut out).unwrap();
+        assert_eq!(String::from_utf8(out).unwrap(), PRETTY);
+    }
+
+    #[test]
+    fn add_mapping_extends_existing_line() {
+        let mut doc = HostsDocument::read_buffered(PRETTY.as_bytes()).unwrap();
+        doc.add_mapping(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), "lh");
+        let hosts: Vec<&str> = doc.lines()[1].hosts().collect();
+        assert_eq!(hosts, vec!["localhost", "localhost.localdomain", "lh"]);
+    }
+
+    #[test]
+    fn add_mapping_creates_new_line() {
+        let mut doc = HostsDocument::read_buffered(PRETTY.as_bytes()).unwrap();
+        let ip = IpAddr::V4(Ipv4Addr::new(10, 0, 0, 1));
+        doc.add_mapping(ip, "example");
+        let last = doc.lines().last().unwrap();
+        assert_eq!(last.ip().unwrap(), ip);
+    }
+
+    #[test]
+    fn remove_host_drops_emptied_line() {
+        let mut doc = HostsDocument::read_buffered(PRETTY.as_bytes()).unwrap();
+        let before = doc.lines().len();
+        doc.remove_host("gdns");
+        assert_eq!(doc.lines().len(), before - 1);
+    }
+
+    #[test]
+    fn remove_host_strips_every_occurrence() {
+        let mut doc = HostsDocument::read_buffered(
+            "127.0.0.1 localhost foo\n10.0.0.1 foo\n".as_bytes(),
+        ).unwrap();
+        doc.remove_host("foo");
+        for line in doc.lines() {
+            if let Some(data) = line.data() {
+                assert!(data.hosts().all(|h| h != "foo"));
+            }
+        }
+    }
+
+    #[test]
+    fn remove_ip_drops_all_lines_for_it() {
+        let mut doc = HostsDocument::read_buffered(PRETTY.as_bytes()).unwrap();
+        doc.remove_ip(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)));
+        assert!(doc.lines().iter().all(|line| {
+            line.ip() != Some(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)))
+        }));
+    }
+
+    #[test]
+    fn set_host_moves_it() {
+        let mut doc = HostsDocument::read_buffered(PRETTY.as_bytes()).unwrap();
+        let new_ip = IpAddr::V4(Ipv4Addr::new(9, 9, 9, 9));
+        doc.set_host("gdns", new_ip);
+        assert_eq!(
+            doc.lines().last().unwrap().ip().unwrap(),
+            new_ip
+        );
+    }
+
+    #[test]
+    fn set_host_resolves_duplicate_occurrences() {
+        let mut doc = HostsDocument::read_buffered(
+            "10.0.0.1 foo\n10.0.0.2 foo\n".as_bytes(),
+        ).unwrap();
+        let new_ip = IpAddr::V4(Ipv4Addr::new(9, 9, 9, 9));
+        doc.set_host("foo", new_ip);
+        let matches: Vec<&super::Line> = doc.lines()
+            .iter()
+            .filter(|line| line.hosts().any(|h| h == "foo"))
+            .collect();
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].ip().unwrap(), new_ip);
+    }
+}
@@ -208,4 +208,32 @@ mod tests {
         assert_eq!(*map.get("gdns").unwrap(), "8.8.8.8".parse::<IpAddr>().unwrap());
         assert_eq!(*map.get("gdns2").unwrap(), "8.8.4.4".parse::<IpAddr>().unwrap());
     }
+
+    static WITH_DISABLED: &str = "\
+127.0.0.1  localhost
+# 1.2.3.4 old
+8.8.8.8  gdns
+";
+
+    #[test]
+    fn data_lines_skip_disabled_entries() {
+        let data: Vec<DataLine> = HostsFile::read_buffered(WITH_DISABLED.as_bytes())
+            .data_lines()
+            .map(Result::unwrap)
+            .collect();
+        assert_eq!(data.len(), 2);
+        assert!(data.iter().all(|line| {
+            line.ip() != "1.2.3.4".parse::<IpAddr>().unwrap()
+        }));
+    }
+
+    #[test]
+    fn pairs_skip_disabled_entries() {
+        let map: HashMap<String, IpAddr> = HostsFile::read_buffered(WITH_DISABLED.as_bytes())
+            .pairs()
+            .map(Result::unwrap)
+            .collect();
+        assert!(!map.contains_key("old"));
+        assert_eq!(map.len(), 2);
+    }
 }
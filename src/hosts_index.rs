@@ -0,0 +1,150 @@
+use std::collections::HashMap;
+use std::io::BufRead;
+use std::net::IpAddr;
+use super::{DataLine, HostsDocument, HostsFile, Line, LineReadError};
+
+/// A forward/reverse lookup index built from a loaded hosts file.
+///
+/// Where `HostsFile` and `HostsDocument` only expose the raw lines, `HostsIndex` flattens them
+/// into a hostname -> `IpAddr` map and an `IpAddr` -> hostnames map, giving consumers a real
+/// lookup layer instead of having to build their own `HashMap` from `Pairs`.
+pub struct HostsIndex {
+    forward: HashMap<String, IpAddr>,
+    reverse: HashMap<IpAddr, Vec<String>>,
+}
+impl HostsIndex {
+    /// Builds an index by consuming a `HostsFile`.
+    pub fn from_file<R: BufRead>(file: HostsFile<R>) -> Result<HostsIndex, LineReadError> {
+        let mut data_lines = Vec::new();
+        for line in file.data_lines() {
+            data_lines.push(line?);
+        }
+        Ok(HostsIndex::from_data_lines(data_lines))
+    }
+
+    /// Builds an index from a `HostsDocument`.
+    pub fn from_document(doc: &HostsDocument) -> HostsIndex {
+        let data_lines: Vec<DataLine> = doc.lines()
+            .iter()
+            .filter_map(Line::data)
+            .cloned()
+            .collect();
+        HostsIndex::from_data_lines(data_lines)
+    }
+
+    fn from_data_lines(data_lines: Vec<DataLine>) -> HostsIndex {
+        let mut forward = HashMap::new();
+        for data in &data_lines {
+            for host in data.hosts() {
+                // Later lines win, matching libc's hosts-file precedence.
+                forward.insert(host.to_ascii_lowercase(), data.ip());
+            }
+        }
+
+        // Derive `reverse` from the original lines (so the original hostname casing survives),
+        // keeping only the hosts whose winning IP per `forward` still matches this line's IP, so
+        // the two directions never disagree about which IP a multiply-defined host resolves to.
+        let mut reverse: HashMap<IpAddr, Vec<String>> = HashMap::new();
+        for data in &data_lines {
+            for host in data.hosts() {
+                if forward.get(&host.to_ascii_lowercase()) == Some(&data.ip()) {
+                    reverse.entry(data.ip()).or_insert_with(Vec::new).push(
+                        host.to_owned(),
+                    );
+                }
+            }
+        }
+        for hosts in reverse.values_mut() {
+            hosts.sort();
+            hosts.dedup();
+        }
+
+        HostsIndex {
+            forward: forward,
+            reverse: reverse,
+        }
+    }
+
+    /// Resolves a hostname to its IP, matching case-insensitively.
+    pub fn resolve(&self, host: &str) -> Option<IpAddr> {
+        self.forward.get(&host.to_ascii_lowercase()).cloned()
+    }
+
+    /// Checks whether a hostname is present in the index.
+    pub fn contains_host(&self, host: &str) -> bool {
+        self.forward.contains_key(&host.to_ascii_lowercase())
+    }
+
+    /// Gets the hostnames mapped to an IP, for PTR-style reverse lookups.
+    pub fn reverse(&self, ip: IpAddr) -> &[String] {
+        self.reverse.get(&ip).map_or(&[], |hosts| &**hosts)
+    }
+
+    /// Gets the hostnames mapped to an IP. Alias for `reverse`.
+    pub fn hosts_for_ip(&self, ip: IpAddr) -> &[String] {
+        self.reverse(ip)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::{IpAddr, Ipv4Addr};
+    use super::HostsIndex;
+    use super::super::HostsFile;
+
+    static PRETTY: &str = "\
+# basic ones
+127.0.0.1  localhost localhost.localdomain
+0.0.0.0  allzeros  # nonstandard
+10.0.0.1  shared
+10.0.0.2  shared
+";
+
+    #[test]
+    fn resolve_is_case_insensitive() {
+        let index = HostsIndex::from_file(HostsFile::read_buffered(PRETTY.as_bytes())).unwrap();
+        assert_eq!(
+            index.resolve("LOCALHOST"),
+            Some(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)))
+        );
+        assert!(index.contains_host("AllZeros"));
+    }
+
+    #[test]
+    fn later_definition_wins() {
+        let index = HostsIndex::from_file(HostsFile::read_buffered(PRETTY.as_bytes())).unwrap();
+        assert_eq!(
+            index.resolve("shared"),
+            Some(IpAddr::V4(Ipv4Addr::new(10, 0, 0, 2)))
+        );
+    }
+
+    #[test]
+    fn reverse_agrees_with_forward_on_override() {
+        let index = HostsIndex::from_file(HostsFile::read_buffered(PRETTY.as_bytes())).unwrap();
+        let old_ip = IpAddr::V4(Ipv4Addr::new(10, 0, 0, 1));
+        let new_ip = IpAddr::V4(Ipv4Addr::new(10, 0, 0, 2));
+        assert_eq!(index.resolve("shared"), Some(new_ip));
+        assert!(!index.reverse(old_ip).iter().any(|h| h == "shared"));
+        assert!(index.reverse(new_ip).iter().any(|h| h == "shared"));
+    }
+
+    #[test]
+    fn reverse_preserves_original_casing() {
+        let index = HostsIndex::from_file(
+            HostsFile::read_buffered("127.0.0.1 LocalHost\n".as_bytes()),
+        ).unwrap();
+        assert_eq!(
+            index.hosts_for_ip(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1))),
+            &["LocalHost"]
+        );
+    }
+
+    #[test]
+    fn reverse_lookup() {
+        let index = HostsIndex::from_file(HostsFile::read_buffered(PRETTY.as_bytes())).unwrap();
+        let hosts = index.hosts_for_ip(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)));
+        assert_eq!(hosts, &["localhost", "localhost.localdomain"]);
+        assert!(index.reverse(IpAddr::V4(Ipv4Addr::new(9, 9, 9, 9))).is_empty());
+    }
+}
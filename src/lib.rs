@@ -4,8 +4,13 @@
 extern crate multistr;
 
 mod data_line;
+mod hosts_document;
 mod hosts_file;
+mod hosts_index;
 mod line;
-pub use data_line::{DataLine, DataParseError, Hosts, minify_lines};
+mod punycode;
+pub use data_line::{DataLine, DataParseError, Hosts, ValidationPolicy, minify_lines};
+pub use hosts_document::*;
 pub use hosts_file::*;
+pub use hosts_index::*;
 pub use line::*;
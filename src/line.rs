@@ -9,6 +9,7 @@ use super::data_line::empty_hosts;
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct Line<'a> {
     data: Option<DataLine>,
+    disabled: Option<DataLine>,
     comment: Option<Cow<'a, str>>,
 }
 impl Line<'static> {
@@ -16,6 +17,7 @@ impl Line<'static> {
     pub fn empty() -> Line<'static> {
         Line {
             data: None,
+            disabled: None,
             comment: None,
         }
     }
@@ -24,6 +26,16 @@ impl Line<'static> {
     pub fn from_data(data: DataLine) -> Line<'static> {
         Line {
             data: Some(data),
+            disabled: None,
+            comment: None,
+        }
+    }
+
+    /// Creates a line directly from a disabled (commented-out) data line.
+    pub fn from_disabled(data: DataLine) -> Line<'static> {
+        Line {
+            data: None,
+            disabled: Some(data),
             comment: None,
         }
     }
@@ -32,9 +44,15 @@ impl Line<'static> {
 
 impl<'a> Line<'a> {
     /// Creates a line from a string.
+    ///
+    /// If the entire line is a comment and the commented-out text itself parses as a
+    /// `DataLine`, the line is treated as a disabled entry; see `is_disabled`. A disabled entry
+    /// may itself carry a trailing comment, found by splitting the commented-out text on its own
+    /// `#` the same way the top-level line was split, so `Display` and `new` round-trip a
+    /// disabled-with-comment line back to the same structured state.
     pub fn new(line: &str) -> Result<Line, DataParseError> {
-        let (comment, stripped) = if let Some(idx) = line.find('#') {
-            (Some(Cow::from(line[idx + 1..].trim_left())), &line[..idx])
+        let (comment_text, stripped) = if let Some(idx) = line.find('#') {
+            (Some(line[idx + 1..].trim_left()), &line[..idx])
         } else {
             (None, line)
         };
@@ -44,9 +62,26 @@ impl<'a> Line<'a> {
         } else {
             Some(stripped.parse()?)
         };
+        if data.is_none() {
+            if let Some(comment_text) = comment_text {
+                let (inner_comment, inner_data_text) = if let Some(idx) = comment_text.find('#') {
+                    (Some(comment_text[idx + 1..].trim_left()), &comment_text[..idx])
+                } else {
+                    (None, comment_text)
+                };
+                if let Ok(disabled) = inner_data_text.trim_right().parse() {
+                    return Ok(Line {
+                        data: None,
+                        disabled: Some(disabled),
+                        comment: inner_comment.map(Cow::from),
+                    });
+                }
+            }
+        }
         Ok(Line {
             data: data,
-            comment: comment,
+            disabled: None,
+            comment: comment_text.map(Cow::from),
         })
     }
 
@@ -54,6 +89,7 @@ impl<'a> Line<'a> {
     pub fn from_comment(comment: &str) -> Line {
         Line {
             data: None,
+            disabled: None,
             comment: Some(comment.into()),
         }
     }
@@ -62,6 +98,7 @@ impl<'a> Line<'a> {
     pub fn from_raw(data: DataLine, comment: &str) -> Line {
         Line {
             data: Some(data),
+            disabled: None,
             comment: Some(comment.into()),
         }
     }
@@ -85,6 +122,35 @@ impl<'a> Line<'a> {
         self.data.as_ref()
     }
 
+    /// Gets the data from this line, mutably.
+    pub fn data_mut(&mut self) -> Option<&mut DataLine> {
+        self.data.as_mut()
+    }
+
+    /// Checks whether this line is a disabled (commented-out) entry.
+    pub fn is_disabled(&self) -> bool {
+        self.disabled.is_some()
+    }
+
+    /// Gets the data of a disabled entry, if this line is one.
+    pub fn disabled_data(&self) -> Option<&DataLine> {
+        self.disabled.as_ref()
+    }
+
+    /// Moves this line's entry between the active and commented-out states.
+    ///
+    /// Passing `true` enables a disabled entry; passing `false` disables an active one.
+    /// Has no effect on plain comments or empty lines.
+    pub fn set_enabled(&mut self, enabled: bool) {
+        if enabled {
+            if let Some(data) = self.disabled.take() {
+                self.data = Some(data);
+            }
+        } else if let Some(data) = self.data.take() {
+            self.disabled = Some(data);
+        }
+    }
+
     /// Gets the comment from this line.
     pub fn comment<'b>(&'b self) -> Option<&'b str>
     where
@@ -102,6 +168,7 @@ impl<'a> Line<'a> {
     pub fn into_owned(self) -> Line<'static> {
         Line {
             data: self.data,
+            disabled: self.disabled,
             comment: self.comment.map(Cow::into_owned).map(Cow::Owned),
         }
     }
@@ -116,11 +183,13 @@ impl FromStr for Line<'static> {
 
 impl<'a> fmt::Display for Line<'a> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        match (self.data(), self.comment()) {
-            (Some(data), Some(comment)) => write!(f, "{}  # {}", data, comment),
-            (None, Some(comment)) => write!(f, "# {}", comment),
-            (Some(data), None) => fmt::Display::fmt(data, f),
-            (None, None) => Ok(()),
+        match (self.data(), self.disabled_data(), self.comment()) {
+            (Some(data), _, Some(comment)) => write!(f, "{}  # {}", data, comment),
+            (Some(data), _, None) => fmt::Display::fmt(data, f),
+            (None, Some(data), Some(comment)) => write!(f, "# {}  # {}", data, comment),
+            (None, Some(data), None) => write!(f, "# {}", data),
+            (None, None, Some(comment)) => write!(f, "# {}", comment),
+            (None, None, None) => Ok(()),
         }
     }
 }
@@ -161,4 +230,51 @@ mod tests {
         let hosts: Vec<&str> = full.hosts().collect();
         assert_eq!(hosts, vec!["localhost", "localhost.localdomain", "lh"]);
     }
+
+    #[test]
+    fn parse_disabled() {
+        let disabled: Line = "# 127.0.0.1 oldserver".parse().unwrap();
+        assert!(disabled.is_disabled());
+        assert!(disabled.data().is_none());
+        assert!(disabled.ip().is_none());
+        assert_eq!(
+            disabled.disabled_data().unwrap().ip(),
+            IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1))
+        );
+    }
+
+    #[test]
+    fn toggle_enabled() {
+        let mut line: Line = "# 127.0.0.1 oldserver".parse().unwrap();
+        assert!(line.is_disabled());
+        line.set_enabled(true);
+        assert!(!line.is_disabled());
+        assert_eq!(line.ip().unwrap(), IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)));
+        line.set_enabled(false);
+        assert!(line.is_disabled());
+        assert!(line.data().is_none());
+    }
+
+    #[test]
+    fn disabled_plain_comment_unaffected() {
+        let comment: Line = "   #   \t what? ".parse().unwrap();
+        assert!(!comment.is_disabled());
+        assert!(comment.disabled_data().is_none());
+    }
+
+    #[test]
+    fn disabling_a_commented_line_preserves_the_comment() {
+        let mut line: Line = "127.0.0.1  localhost  # keep me".parse().unwrap();
+        line.set_enabled(false);
+        assert!(line.is_disabled());
+        let rendered = line.to_string();
+        assert!(
+            rendered.contains("keep me"),
+            "comment was dropped: {:?}",
+            rendered
+        );
+        let reparsed: Line = rendered.parse().unwrap();
+        assert!(reparsed.is_disabled());
+        assert_eq!(reparsed.comment().unwrap(), "keep me");
+    }
 }
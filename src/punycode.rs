@@ -0,0 +1,118 @@
+//! Minimal RFC 3492 bootstring (punycode) encoder.
+//!
+//! Used to normalize non-ASCII hostname labels to the ASCII-compatible (`xn--`) form the system
+//! resolver actually looks up, following the parameters punycode itself uses: `base = 36`,
+//! `tmin = 1`, `tmax = 26`, `skew = 38`, `damp = 700`, `initial_bias = 72`, `initial_n = 128`.
+
+const BASE: u32 = 36;
+const TMIN: u32 = 1;
+const TMAX: u32 = 26;
+const SKEW: u32 = 38;
+const DAMP: u32 = 700;
+const INITIAL_BIAS: u32 = 72;
+const INITIAL_N: u32 = 128;
+
+fn adapt(delta: u32, num_points: u32, first_time: bool) -> u32 {
+    let mut delta = if first_time { delta / DAMP } else { delta / 2 };
+    delta += delta / num_points;
+    let mut k = 0;
+    while delta > ((BASE - TMIN) * TMAX) / 2 {
+        delta /= BASE - TMIN;
+        k += BASE;
+    }
+    k + (((BASE - TMIN + 1) * delta) / (delta + SKEW))
+}
+
+fn encode_digit(d: u32) -> u8 {
+    if d < 26 { b'a' + d as u8 } else { b'0' + (d - 26) as u8 }
+}
+
+/// Bootstring-encodes the code points of `input`, returning the suffix that follows `xn--`.
+///
+/// Returns `None` if the delta arithmetic overflows, which only happens for pathologically large
+/// or spread-out inputs.
+fn encode(input: &str) -> Option<String> {
+    let code_points: Vec<u32> = input.chars().map(|c| c as u32).collect();
+    let mut output: Vec<u8> = code_points
+        .iter()
+        .cloned()
+        .filter(|&c| c < 0x80)
+        .map(|c| c as u8)
+        .collect();
+    let basic_len = output.len();
+    let mut h = basic_len;
+    if basic_len > 0 {
+        output.push(b'-');
+    }
+
+    let mut n = INITIAL_N;
+    let mut delta: u32 = 0;
+    let mut bias = INITIAL_BIAS;
+    let total = code_points.len();
+
+    while h < total {
+        let m = code_points.iter().cloned().filter(|&c| c >= n).min()?;
+        delta = delta.checked_add((m - n).checked_mul(h as u32 + 1)?)?;
+        n = m;
+        for &c in &code_points {
+            if c < n {
+                delta = delta.checked_add(1)?;
+            }
+            if c == n {
+                let mut q = delta;
+                let mut k = BASE;
+                loop {
+                    let t = if k <= bias {
+                        TMIN
+                    } else if k >= bias + TMAX {
+                        TMAX
+                    } else {
+                        k - bias
+                    };
+                    if q < t {
+                        break;
+                    }
+                    output.push(encode_digit(t + (q - t) % (BASE - t)));
+                    q = (q - t) / (BASE - t);
+                    k += BASE;
+                }
+                output.push(encode_digit(q));
+                bias = adapt(delta, h as u32 + 1, h == basic_len);
+                delta = 0;
+                h += 1;
+            }
+        }
+        delta = delta.checked_add(1)?;
+        n += 1;
+    }
+
+    Some(String::from_utf8(output).expect("encode_digit only emits ASCII"))
+}
+
+/// Encodes a single hostname label to its ASCII-compatible form.
+///
+/// Pure-ASCII labels are returned unchanged; labels with non-ASCII code points are bootstring
+/// encoded and prefixed with `xn--`. Returns `None` if the label can't be encoded.
+pub fn encode_label(label: &str) -> Option<String> {
+    if label.is_ascii() {
+        Some(label.to_owned())
+    } else {
+        encode(label).map(|suffix| format!("xn--{}", suffix))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::encode_label;
+
+    #[test]
+    fn ascii_label_unchanged() {
+        assert_eq!(encode_label("example").as_ref().map(|s| &**s), Some("example"));
+    }
+
+    #[test]
+    fn encodes_muller() {
+        // m\u{fc}ller.example -> xn--mller-kva.example, per the canonical IDNA test vector.
+        assert_eq!(encode_label("m\u{fc}ller").as_ref().map(|s| &**s), Some("xn--mller-kva"));
+    }
+}